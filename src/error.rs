@@ -0,0 +1,71 @@
+//! A typed error covering every failure point this crate's functions can hit, so callers can
+//! match on a specific failure mode instead of string-matching an opaque error message.
+
+use std::fmt;
+
+/// Errors returned by this crate's functions.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum WinverError {
+    /// `path` contained an interior NUL code unit once encoded as UTF-16, at the given index,
+    /// which can't be passed to a Win32 API expecting a null-terminated string.
+    InteriorNul { position: usize },
+    /// `GetFileVersionInfoSizeW` reported no version info. `code` is the result of
+    /// `GetLastError()`, which distinguishes a missing/inaccessible file from a file that simply
+    /// has no `VS_VERSIONINFO` resource.
+    #[cfg(windows)]
+    NoVersionInfo { code: u32 },
+    /// `GetFileVersionInfoW` failed to read the version info data. `code` is the result of
+    /// `GetLastError()`.
+    #[cfg(windows)]
+    ReadFailed { code: u32 },
+    /// `VerQueryValueW` failed to find the requested resource sub-block. `code` is the result of
+    /// `GetLastError()`.
+    #[cfg(windows)]
+    QueryFailed { code: u32 },
+    /// The root block didn't start with the `VS_FIXEDFILEINFO` signature `0xFEEF04BD`, meaning
+    /// this isn't a valid `VS_VERSIONINFO` resource.
+    BadSignature { found: u32 },
+    /// The `VS_FIXEDFILEINFO` block was too small to contain the fields this crate reads.
+    TruncatedFixedInfo { expected: usize, got: usize },
+    /// The `VS_VERSIONINFO` node tree is malformed in a way not covered by a more specific
+    /// variant above, e.g. a truncated node header, an unterminated key, or a value that
+    /// extends past the end of its node.
+    Malformed { reason: &'static str },
+}
+
+impl fmt::Display for WinverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InteriorNul { position } => {
+                write!(f, "interior NUL byte at position {}", position)
+            }
+            #[cfg(windows)]
+            Self::NoVersionInfo { code } => {
+                write!(f, "GetFileVersionInfoSizeW failed, GetLastError() = {}", code)
+            }
+            #[cfg(windows)]
+            Self::ReadFailed { code } => {
+                write!(f, "GetFileVersionInfoW failed, GetLastError() = {}", code)
+            }
+            #[cfg(windows)]
+            Self::QueryFailed { code } => {
+                write!(f, "VerQueryValueW failed, GetLastError() = {}", code)
+            }
+            Self::BadSignature { found } => {
+                write!(f, "unexpected VS_FIXEDFILEINFO signature {:#x}", found)
+            }
+            Self::TruncatedFixedInfo { expected, got } => write!(
+                f,
+                "truncated VS_FIXEDFILEINFO: expected {} bytes, got {}",
+                expected, got
+            ),
+            Self::Malformed { reason } => write!(f, "malformed version info resource: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for WinverError {}
+
+/// A convenience alias for `Result`s returned by this crate's functions.
+pub type Result<T> = std::result::Result<T, WinverError>;
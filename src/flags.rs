@@ -0,0 +1,250 @@
+//! Typed decodings of the `file_flags`, `file_os`, `file_type`, and `file_subtype` fields of
+//! [FixedFileInfo](crate::FixedFileInfo), so callers don't need to memorize the Win32
+//! `VS_FF_*`/`VOS_*`/`VFT_*`/`VFT2_*` constants documented at
+//! https://docs.microsoft.com/en-us/windows/win32/api/verrsrc/ns-verrsrc-vs_fixedfileinfo
+
+use std::fmt;
+
+use bitflags::bitflags;
+
+bitflags! {
+    /// `dwFileFlags` bits from a `VS_FIXEDFILEINFO` block. Use
+    /// [FixedFileInfo::flags](crate::FixedFileInfo::flags) to get a value already masked by
+    /// `dwFileFlagsMask`, rather than constructing this directly from the raw field.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct FileFlags: u32 {
+        /// The file contains debugging information, or is compiled with debugging features enabled.
+        const DEBUG = 0x0000_0001;
+        /// The file is a development version, not a commercially released product.
+        const PRERELEASE = 0x0000_0002;
+        /// The file has been modified and is not identical to the original shipping file of the
+        /// same version number.
+        const PATCHED = 0x0000_0004;
+        /// The file was not built using standard release procedures.
+        const PRIVATEBUILD = 0x0000_0008;
+        /// The file's version structure was created dynamically; some fields in it may be empty
+        /// or incorrect.
+        const INFOINFERRED = 0x0000_0010;
+        /// The file is a variation of the normal file of the same version number.
+        const SPECIALBUILD = 0x0000_0020;
+    }
+}
+
+impl fmt::Display for FileFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const NAMES: &[(FileFlags, &str)] = &[
+            (FileFlags::DEBUG, "debug"),
+            (FileFlags::PRERELEASE, "prerelease"),
+            (FileFlags::PATCHED, "patched"),
+            (FileFlags::PRIVATEBUILD, "private build"),
+            (FileFlags::INFOINFERRED, "info inferred"),
+            (FileFlags::SPECIALBUILD, "special build"),
+        ];
+        let mut wrote_any = false;
+        for &(flag, name) in NAMES {
+            if self.contains(flag) {
+                if wrote_any {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", name)?;
+                wrote_any = true;
+            }
+        }
+        if !wrote_any {
+            write!(f, "none")?;
+        }
+        Ok(())
+    }
+}
+
+/// The operating system (and windowing system, where applicable) a file was designed for,
+/// decoded from the `dwFileOS` field of a `VS_FIXEDFILEINFO` block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileOs {
+    Unknown,
+    DosWindows16,
+    DosWindows32,
+    Os216Pm16,
+    Os232Pm32,
+    NtWindows32,
+    /// A `dwFileOS` value not recognized here.
+    Other(u32),
+}
+
+impl From<u32> for FileOs {
+    fn from(raw: u32) -> Self {
+        match raw {
+            0x0000_0000 => Self::Unknown,
+            0x0001_0001 => Self::DosWindows16,
+            0x0001_0004 => Self::DosWindows32,
+            0x0002_0002 => Self::Os216Pm16,
+            0x0003_0003 => Self::Os232Pm32,
+            0x0004_0004 => Self::NtWindows32,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for FileOs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Unknown => "unknown OS",
+            Self::DosWindows16 => "MS-DOS, Windows 16-bit",
+            Self::DosWindows32 => "MS-DOS, Win32",
+            Self::Os216Pm16 => "16-bit OS/2, Presentation Manager 16-bit",
+            Self::Os232Pm32 => "32-bit OS/2, Presentation Manager 32-bit",
+            Self::NtWindows32 => "Windows NT, Win32",
+            Self::Other(raw) => return write!(f, "unknown OS (0x{:08x})", raw),
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The general type of a file, decoded from the `dwFileType` field of a `VS_FIXEDFILEINFO`
+/// block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    Unknown,
+    App,
+    Dll,
+    Drv,
+    Font,
+    Vxd,
+    StaticLib,
+    /// A `dwFileType` value not recognized here.
+    Other(u32),
+}
+
+impl From<u32> for FileType {
+    fn from(raw: u32) -> Self {
+        match raw {
+            0x0000_0000 => Self::Unknown,
+            0x0000_0001 => Self::App,
+            0x0000_0002 => Self::Dll,
+            0x0000_0003 => Self::Drv,
+            0x0000_0004 => Self::Font,
+            0x0000_0005 => Self::Vxd,
+            0x0000_0007 => Self::StaticLib,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for FileType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Unknown => "unknown",
+            Self::App => "application",
+            Self::Dll => "DLL",
+            Self::Drv => "driver",
+            Self::Font => "font",
+            Self::Vxd => "virtual device driver",
+            Self::StaticLib => "static library",
+            Self::Other(raw) => return write!(f, "unknown file type (0x{:08x})", raw),
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The function of a `VFT_DRV` file, decoded from `dwFileSubtype`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriverSubtype {
+    Printer,
+    Keyboard,
+    Language,
+    Display,
+    Mouse,
+    Network,
+    System,
+    Installable,
+    Sound,
+    Comm,
+    InputMethod,
+    VersionedPrinter,
+}
+
+impl fmt::Display for DriverSubtype {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Printer => "printer driver",
+            Self::Keyboard => "keyboard driver",
+            Self::Language => "language driver",
+            Self::Display => "display driver",
+            Self::Mouse => "mouse driver",
+            Self::Network => "network driver",
+            Self::System => "system driver",
+            Self::Installable => "installable driver",
+            Self::Sound => "sound driver",
+            Self::Comm => "communications driver",
+            Self::InputMethod => "input method driver",
+            Self::VersionedPrinter => "versioned printer driver",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The function of a `VFT_FONT` file, decoded from `dwFileSubtype`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontSubtype {
+    Raster,
+    Vector,
+    TrueType,
+}
+
+impl fmt::Display for FontSubtype {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Raster => "raster font",
+            Self::Vector => "vector font",
+            Self::TrueType => "TrueType font",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The function of a file, decoded from `dwFileSubtype` relative to its [FileType].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileSubtype {
+    /// `dwFileSubtype` is zero.
+    Unknown,
+    Driver(DriverSubtype),
+    Font(FontSubtype),
+    /// A nonzero `dwFileSubtype` value not recognized for its file type, including any nonzero
+    /// value for a file type other than `VFT_DRV` or `VFT_FONT`.
+    Other(u32),
+}
+
+impl FileSubtype {
+    pub(crate) fn decode(file_type: FileType, raw: u32) -> Self {
+        match (file_type, raw) {
+            (_, 0) => Self::Unknown,
+            (FileType::Drv, 0x1) => Self::Driver(DriverSubtype::Printer),
+            (FileType::Drv, 0x2) => Self::Driver(DriverSubtype::Keyboard),
+            (FileType::Drv, 0x3) => Self::Driver(DriverSubtype::Language),
+            (FileType::Drv, 0x4) => Self::Driver(DriverSubtype::Display),
+            (FileType::Drv, 0x5) => Self::Driver(DriverSubtype::Mouse),
+            (FileType::Drv, 0x6) => Self::Driver(DriverSubtype::Network),
+            (FileType::Drv, 0x7) => Self::Driver(DriverSubtype::System),
+            (FileType::Drv, 0x8) => Self::Driver(DriverSubtype::Installable),
+            (FileType::Drv, 0x9) => Self::Driver(DriverSubtype::Sound),
+            (FileType::Drv, 0xa) => Self::Driver(DriverSubtype::Comm),
+            (FileType::Drv, 0xb) => Self::Driver(DriverSubtype::InputMethod),
+            (FileType::Drv, 0xc) => Self::Driver(DriverSubtype::VersionedPrinter),
+            (FileType::Font, 0x1) => Self::Font(FontSubtype::Raster),
+            (FileType::Font, 0x2) => Self::Font(FontSubtype::Vector),
+            (FileType::Font, 0x3) => Self::Font(FontSubtype::TrueType),
+            (_, other) => Self::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for FileSubtype {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "none"),
+            Self::Driver(d) => write!(f, "{}", d),
+            Self::Font(ft) => write!(f, "{}", ft),
+            Self::Other(raw) => write!(f, "unknown subtype (0x{:08x})", raw),
+        }
+    }
+}
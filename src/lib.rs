@@ -1,33 +1,29 @@
+#[cfg(windows)]
 use std::ffi::OsStr;
 use std::fmt;
+#[cfg(windows)]
 use std::mem::size_of;
+#[cfg(windows)]
 use std::os::windows::ffi::OsStrExt;
+#[cfg(windows)]
 use std::ptr;
+use std::time::{Duration, SystemTime};
 
-use anyhow::{anyhow, Result};
+#[cfg(windows)]
 use winapi::ctypes::c_void;
+#[cfg(windows)]
 use winapi::shared::minwindef::{DWORD, UINT};
+#[cfg(windows)]
+use winapi::um::errhandlingapi::GetLastError;
+#[cfg(windows)]
 use winapi::um::winver::{GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW};
 
-/// VS_FIXEDFILEINFO, C version
-#[repr(C)]
-#[allow(non_snake_case)]
-#[derive(Copy, Clone, Debug, Default)]
-struct RawFixedFileInfo {
-    pub dwSignature: DWORD,
-    pub dwStrucVersion: DWORD,
-    pub dwFileVersionMS: DWORD,
-    pub dwFileVersionLS: DWORD,
-    pub dwProductVersionMS: DWORD,
-    pub dwProductVersionLS: DWORD,
-    pub dwFileFlagsMask: DWORD,
-    pub dwFileFlags: DWORD,
-    pub dwFileOS: DWORD,
-    pub dwFileType: DWORD,
-    pub dwFileSubtype: DWORD,
-    pub dwFileDateMS: DWORD,
-    pub dwFileDateLS: DWORD,
-}
+mod error;
+mod flags;
+mod parse;
+pub use error::{Result, WinverError};
+pub use flags::{DriverSubtype, FileFlags, FileOs, FileSubtype, FileType, FontSubtype};
+pub use parse::{parse_version_info, VersionInfo};
 
 /// A Rust representation of [VS_FIXEDFILEINFO].
 ///
@@ -54,25 +50,45 @@ pub struct FixedFileInfo {
     pub file_date: u64,
 }
 
-impl From<RawFixedFileInfo> for FixedFileInfo {
-    #[rustfmt::skip]
-    fn from(r: RawFixedFileInfo) -> Self {
-        #[inline]
-        fn combine_dwords(high: u32, low: u32) -> u64 {
-            ((high as u64) << 32) | (low as u64)
-        }
+impl FixedFileInfo {
+    /// The file_flags bits that are actually meaningful, i.e. file_flags masked by
+    /// file_flags_mask.
+    pub fn flags(&self) -> FileFlags {
+        FileFlags::from_bits_truncate(self.file_flags & self.file_flags_mask)
+    }
 
-        Self {
-            struc_version:   r.dwSignature,
-            file_version:    combine_dwords(r.dwFileVersionMS, r.dwFileVersionLS),
-            product_version: combine_dwords(r.dwProductVersionMS, r.dwProductVersionLS),
-            file_flags_mask: r.dwFileFlagsMask,
-            file_flags:      r.dwFileFlags,
-            file_os:         r.dwFileOS,
-            file_type:       r.dwFileType,
-            file_subtype:    r.dwFileSubtype,
-            file_date:       combine_dwords(r.dwFileDateMS, r.dwFileDateLS),
+    /// The operating system this file was designed for.
+    pub fn os(&self) -> FileOs {
+        FileOs::from(self.file_os)
+    }
+
+    /// The general type of this file.
+    pub fn kind(&self) -> FileType {
+        FileType::from(self.file_type)
+    }
+
+    /// This file's function, interpreted relative to [kind](Self::kind).
+    pub fn subtype(&self) -> FileSubtype {
+        FileSubtype::decode(self.kind(), self.file_subtype)
+    }
+
+    /// The binary's creation date/time, if the linker set one. `file_date` is a Windows
+    /// `FILETIME`: 100-nanosecond ticks since 1601-01-01 UTC. Linkers commonly leave this field
+    /// zero, in which case `None` is returned.
+    pub fn file_date(&self) -> Option<SystemTime> {
+        if self.file_date == 0 {
+            return None;
         }
+
+        // FILETIME ticks (100ns) between 1601-01-01 and the Unix epoch, 1970-01-01.
+        const EPOCH_DIFF_100NS: i128 = 116_444_736_000_000_000;
+        let nanos_since_unix_epoch = (self.file_date as i128 - EPOCH_DIFF_100NS) * 100;
+        let duration = Duration::from_nanos(nanos_since_unix_epoch.unsigned_abs() as u64);
+        Some(if nanos_since_unix_epoch >= 0 {
+            SystemTime::UNIX_EPOCH + duration
+        } else {
+            SystemTime::UNIX_EPOCH - duration
+        })
     }
 }
 
@@ -110,7 +126,7 @@ impl fmt::Display for Version {
 /// Returns a Vec<u16> of the encoded and null-terminated string, or an Error indicating that there
 /// was an inner null byte in the source, which is illegal. The error value is the index of the
 /// first inner null byte.
-// TODO: return an actual error object
+#[cfg(windows)]
 fn to_wide_string<S>(s: &S) -> Result<Vec<u16>>
 where
     S: AsRef<OsStr> + ?Sized,
@@ -120,7 +136,7 @@ where
     // check for inner null bytes
     for (i, c) in v.iter().enumerate() {
         if *c == 0 {
-            return Err(anyhow!("inner nullbyte at position {}", i));
+            return Err(WinverError::InteriorNul { position: i });
         }
     }
     // append null terminator
@@ -129,6 +145,7 @@ where
 }
 
 /// Call GetFileVersionInfoW and return the raw data buffer as a boxed slice
+#[cfg(windows)]
 fn get_version_data<S>(path: &S) -> Result<Box<[u8]>>
 where
     S: AsRef<OsStr> + ?Sized,
@@ -138,7 +155,9 @@ where
     // DWORD GetFileVersionInfoSizeW(LPCWSTR lptstrFilename, LPDWORD lpdwHandle);
     let size = unsafe { GetFileVersionInfoSizeW(path_w.as_ptr(), &mut handle) };
     if size == 0 {
-        return Err(anyhow!("GetFileVersionInfoSizeW failed"));
+        return Err(WinverError::NoVersionInfo {
+            code: unsafe { GetLastError() },
+        });
     }
 
     let mut buf = vec![0u8; size as usize];
@@ -147,15 +166,19 @@ where
     // Safety: lpData must be valid for dwLen bytes
     let ret = unsafe { GetFileVersionInfoW(path_w.as_ptr(), 0, size, buf.as_mut_ptr() as *mut _) };
     match ret {
-        0 => Err(anyhow!("GetFileVersionInfoW failed")),
+        0 => Err(WinverError::ReadFailed {
+            code: unsafe { GetLastError() },
+        }),
         _ => Ok(buf.into_boxed_slice()),
     }
 }
 
-/// Call VerQueryValueW to vet the root-block FixedFileInfo data.
+/// Call VerQueryValueW to fetch the root-block FixedFileInfo bytes, then hand them to the same
+/// pure-Rust parser [parse_version_info] uses.
 ///
 /// Safety: vdata must contain data that was returned successfully from GetFileVersionInfoW.
 /// A pointer to vdata will be passed to VerQueryValue with no size checking.
+#[cfg(windows)]
 unsafe fn get_fixed_info(vdata: &[u8]) -> Result<FixedFileInfo> {
     let mut pinfo: *mut c_void = ptr::null_mut();
     let mut pinfo_size: UINT = 0;
@@ -176,40 +199,231 @@ unsafe fn get_fixed_info(vdata: &[u8]) -> Result<FixedFileInfo> {
     );
 
     // error checking
-    if ret == 0 {
-        return Err(anyhow!("VerQueryValueW failed"));
+    if ret == 0 || pinfo.is_null() {
+        return Err(WinverError::QueryFailed {
+            code: GetLastError(),
+        });
     }
-    if pinfo.is_null() {
-        return Err(anyhow!("Got null result from VerQueryValueA"));
+
+    // Safety: VerQueryValueW reported pinfo_size bytes are valid at pinfo
+    let bytes = std::slice::from_raw_parts(pinfo as *const u8, pinfo_size as usize);
+    parse::parse_fixed_file_info(bytes)
+}
+
+/// Get the root-level fixed info for a file
+#[cfg(windows)]
+pub fn get_file_fixed_info<S>(path: &S) -> Result<FixedFileInfo>
+where
+    S: AsRef<OsStr> + ?Sized,
+{
+    let data = get_version_data(path)?;
+    unsafe { get_fixed_info(&data) }
+}
+
+/// Call VerQueryValueW to read a single string from a `\StringFileInfo\<lang><codepage>\<key>`
+/// sub-block.
+///
+/// Safety: vdata must contain data that was returned successfully from GetFileVersionInfoW.
+/// A pointer into vdata will be passed to VerQueryValue with no size checking.
+#[cfg(windows)]
+unsafe fn get_string_value(vdata: &[u8], lang_codepage: &str, key: &str) -> Result<String> {
+    let mut pvalue: *mut c_void = ptr::null_mut();
+    let mut value_len: UINT = 0;
+    let sub_block = to_wide_string(&format!("\\StringFileInfo\\{}\\{}", lang_codepage, key))?;
+
+    // BOOL VerQueryValue(LPCVOID pBlock, LPCWSTR lpSubBlock, LPVOID *lplpBuffer, PUINT puLen);
+    // lplpBuffer receives a pointer to a (possibly not null-terminated) UTF-16 string, and puLen
+    // receives its length in WCHARs.
+    //
+    // Safety: pvalue points somewhere inside vdata, don't let it outlive this function
+    let ret = VerQueryValueW(
+        vdata.as_ptr() as *const _,
+        sub_block.as_ptr(),
+        &mut pvalue,
+        &mut value_len,
+    );
+
+    if ret == 0 || pvalue.is_null() {
+        return Err(WinverError::QueryFailed {
+            code: GetLastError(),
+        });
     }
-    if (pinfo_size as usize) < size_of::<RawFixedFileInfo>() {
-        return Err(anyhow!(
-            "Not enough RawFixedFileInfo data. Expected {} got {}",
-            size_of::<RawFixedFileInfo>(),
-            pinfo_size
-        ));
+
+    // the string may or may not include its null terminator in value_len, so trim at the first
+    // nul we find rather than trusting the reported length.
+    let slice = std::slice::from_raw_parts(pvalue as *const u16, value_len as usize);
+    let end = slice.iter().position(|&c| c == 0).unwrap_or(slice.len());
+    Ok(String::from_utf16_lossy(&slice[..end]))
+}
+
+/// Query a single string value from a file's VS_VERSIONINFO resource.
+///
+/// `lang_codepage` is the 8-hex-digit `<lang><codepage>` key naming the StringTable sub-block,
+/// e.g. `"040904b0"` for US English with a Unicode codepage. `key` is the StringTable entry name,
+/// e.g. `"ProductName"`. See [StringInfo] for a convenience wrapper around the common keys.
+#[cfg(windows)]
+pub fn get_file_string_info<S>(path: &S, lang_codepage: &str, key: &str) -> Result<String>
+where
+    S: AsRef<OsStr> + ?Sized,
+{
+    let data = get_version_data(path)?;
+    unsafe { get_string_value(&data, lang_codepage, key) }
+}
+
+/// Call VerQueryValueW to read the `\VarFileInfo\Translation` block out of an already-fetched
+/// version info buffer.
+///
+/// Safety: vdata must contain data that was returned successfully from GetFileVersionInfoW.
+/// A pointer into vdata will be passed to VerQueryValue with no size checking.
+#[cfg(windows)]
+unsafe fn get_translations_from_vdata(vdata: &[u8]) -> Result<Vec<(u16, u16)>> {
+    let mut ptable: *mut c_void = ptr::null_mut();
+    let mut table_size: UINT = 0;
+    let sub_block = to_wide_string("\\VarFileInfo\\Translation")?;
+
+    // BOOL VerQueryValue(LPCVOID pBlock, LPCWSTR lpSubBlock, LPVOID *lplpBuffer, PUINT puLen);
+    // lplpBuffer receives a pointer to an array of DWORDs, each packing a language ID in the low
+    // word and a codepage in the high word. puLen receives the array's size in bytes.
+    //
+    // Safety: ptable points somewhere inside vdata, don't let it outlive this function
+    let ret = VerQueryValueW(
+        vdata.as_ptr() as *const _,
+        sub_block.as_ptr(),
+        &mut ptable,
+        &mut table_size,
+    );
+
+    if ret == 0 || ptable.is_null() {
+        return Err(WinverError::QueryFailed {
+            code: GetLastError(),
+        });
     }
 
-    // safety: use an unaligned write because we don't know for sure that the raw block is properly
-    // aligned. Maybe this is excess paranoia?
-    let raw_info = ptr::read_unaligned(pinfo as *const RawFixedFileInfo);
+    let count = table_size as usize / size_of::<u32>();
+    let entries = std::slice::from_raw_parts(ptable as *const u32, count);
+    Ok(entries
+        .iter()
+        .map(|&dw| ((dw & 0xffff) as u16, (dw >> 16) as u16))
+        .collect())
+}
+
+/// Get the `(language, codepage)` pairs a file's `VS_VERSIONINFO` resource declares StringTables
+/// for, read from the `\VarFileInfo\Translation` block.
+#[cfg(windows)]
+pub fn get_translations<S>(path: &S) -> Result<Vec<(u16, u16)>>
+where
+    S: AsRef<OsStr> + ?Sized,
+{
+    let data = get_version_data(path)?;
+    unsafe { get_translations_from_vdata(&data) }
+}
 
-    // the signature is supposed to be this magic number
-    if raw_info.dwSignature != 0xfeef04bd {
-        return Err(anyhow!(
-            "Unexpected VS_FILEINFO signature {:x}",
-            raw_info.dwSignature
-        ));
+/// Format a `(language, codepage)` pair as the 8-hex-digit `<lang><codepage>` key used to name
+/// StringTable sub-blocks. Note this is the opposite word order from the packed translation
+/// table DWORD, where the language is the low word and the codepage is the high word.
+#[cfg(windows)]
+fn format_lang_codepage(lang: u16, codepage: u16) -> String {
+    format!("{:04x}{:04x}", lang, codepage)
+}
+
+/// Common fallback `<lang><codepage>` keys, tried in order after the caller's preferred language
+/// and the file's first declared translation, matching the fallback behavior version.dll
+/// consumers conventionally implement by hand.
+#[cfg(windows)]
+const FALLBACK_LANG_CODEPAGES: &[&str] = &["040904b0", "040904e4"];
+
+/// Build the ordered list of `<lang><codepage>` keys to try when looking up a StringTable value:
+/// the caller's preferred pair (if given), then the file's first declared translation, then the
+/// common US English keys.
+#[cfg(windows)]
+unsafe fn lang_codepage_candidates(vdata: &[u8], preferred: Option<(u16, u16)>) -> Vec<String> {
+    let mut candidates = Vec::new();
+    if let Some((lang, codepage)) = preferred {
+        candidates.push(format_lang_codepage(lang, codepage));
     }
+    if let Ok(translations) = get_translations_from_vdata(vdata) {
+        if let Some(&(lang, codepage)) = translations.first() {
+            candidates.push(format_lang_codepage(lang, codepage));
+        }
+    }
+    candidates.extend(FALLBACK_LANG_CODEPAGES.iter().map(|s| s.to_string()));
+    candidates
+}
 
-    Ok(FixedFileInfo::from(raw_info))
+/// Look up a single StringTable value, trying `preferred` first and falling back through the
+/// file's declared translations and the common US English keys as described by
+/// [lang_codepage_candidates].
+#[cfg(windows)]
+unsafe fn get_string_value_fallback(
+    vdata: &[u8],
+    preferred: Option<(u16, u16)>,
+    key: &str,
+) -> Result<String> {
+    let mut last_err = WinverError::QueryFailed { code: 0 };
+    for lang_codepage in lang_codepage_candidates(vdata, preferred) {
+        match get_string_value(vdata, &lang_codepage, key) {
+            Ok(value) => return Ok(value),
+            Err(e) => last_err = e,
+        }
+    }
+    Err(last_err)
 }
 
-/// Get the root-level fixed info for a file
-pub fn get_file_fixed_info<S>(path: &S) -> Result<FixedFileInfo>
+/// The common StringTable values found under a `VS_VERSIONINFO` resource's `StringFileInfo`
+/// block.
+///
+/// Any key not present in the file is left as `None` rather than causing the whole lookup to
+/// fail, since producers are free to omit any of these.
+#[derive(Debug, Clone, Default)]
+pub struct StringInfo {
+    pub comments: Option<String>,
+    pub company_name: Option<String>,
+    pub file_description: Option<String>,
+    pub file_version: Option<String>,
+    pub internal_name: Option<String>,
+    pub legal_copyright: Option<String>,
+    pub legal_trademarks: Option<String>,
+    pub original_filename: Option<String>,
+    pub private_build: Option<String>,
+    pub product_name: Option<String>,
+    pub product_version: Option<String>,
+    pub special_build: Option<String>,
+}
+
+#[cfg(windows)]
+impl StringInfo {
+    /// Read every well-known StringTable value out of an already-fetched version info buffer,
+    /// using the same language fallback as [get_string_value_fallback] for each key.
+    unsafe fn from_vdata(vdata: &[u8], preferred: Option<(u16, u16)>) -> Self {
+        let get = |key: &str| get_string_value_fallback(vdata, preferred, key).ok();
+        Self {
+            comments: get("Comments"),
+            company_name: get("CompanyName"),
+            file_description: get("FileDescription"),
+            file_version: get("FileVersion"),
+            internal_name: get("InternalName"),
+            legal_copyright: get("LegalCopyright"),
+            legal_trademarks: get("LegalTrademarks"),
+            original_filename: get("OriginalFilename"),
+            private_build: get("PrivateBuild"),
+            product_name: get("ProductName"),
+            product_version: get("ProductVersion"),
+            special_build: get("SpecialBuild"),
+        }
+    }
+}
+
+/// Get all well-known StringTable values for a file.
+///
+/// `preferred`, if given, is tried first. Otherwise (or if it isn't present) the file's first
+/// declared translation is used, then the common `040904B0` (US English, Unicode) and
+/// `040904E4` keys, matching the fallback real-world version.dll consumers implement. Use
+/// [get_translations] to discover which pairs a file actually ships.
+#[cfg(windows)]
+pub fn get_file_string_table<S>(path: &S, preferred: Option<(u16, u16)>) -> Result<StringInfo>
 where
     S: AsRef<OsStr> + ?Sized,
 {
     let data = get_version_data(path)?;
-    unsafe { get_fixed_info(&data) }
+    Ok(unsafe { StringInfo::from_vdata(&data, preferred) })
 }
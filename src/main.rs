@@ -1,9 +1,12 @@
 use std::process::exit;
 
+#[cfg(windows)]
 use winver::{get_file_fixed_info, Version};
 
+#[cfg(windows)]
 const DEFAULT_PATH: &str = r"C:\Program Files\Vim\vim82\gvim.exe";
 
+#[cfg(windows)]
 fn print_one(path: &str) -> u32 {
     match get_file_fixed_info(path) {
         Ok(info) => {
@@ -17,6 +20,7 @@ fn print_one(path: &str) -> u32 {
     }
 }
 
+#[cfg(windows)]
 fn main() {
     let mut any = false;
     let mut err = 0;
@@ -32,3 +36,12 @@ fn main() {
         exit(1);
     }
 }
+
+/// `get_file_fixed_info` and friends call into Win32 APIs directly, so this CLI has nothing to do
+/// on other platforms; use `winver::parse_version_info` as a library on a resource buffer
+/// obtained some other way (e.g. extracted from a PE file).
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("winver: this CLI only supports Windows; see winver::parse_version_info for other platforms");
+    exit(1);
+}
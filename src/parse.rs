@@ -0,0 +1,325 @@
+//! Pure-Rust parsing of a `VS_VERSIONINFO` resource tree, with no dependency on Win32 APIs.
+//!
+//! This lets the tree be parsed from a buffer obtained any way (e.g. extracted from a PE file's
+//! `RT_VERSION` resource by another crate), not just via `GetFileVersionInfoW` on Windows. The
+//! node layout below follows the Wine/ReactOS `version.dll` implementation.
+
+use std::collections::HashMap;
+
+use crate::{FixedFileInfo, Result, WinverError};
+
+/// Round `n` up to the next multiple of 4, as VS_VERSIONINFO nodes pad their key and value to
+/// 32-bit boundaries.
+fn align4(n: usize) -> usize {
+    (n + 3) & !3
+}
+
+/// One generically-parsed node of the VS_VERSIONINFO tree. Every block (`VS_VERSIONINFO`,
+/// `StringFileInfo`, a `StringTable`, a `String`, `VarFileInfo`, a `Var`) shares this layout:
+/// `WORD wLength`, `WORD wValueLength`, `WORD wType`, a null-terminated UTF-16 `szKey`, padding,
+/// the value, padding, then child nodes.
+#[derive(Debug)]
+struct Node<'a> {
+    key: String,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+/// Parse a single node starting at the beginning of `data`. Returns the node and its total
+/// length in bytes (`wLength`, unpadded).
+fn parse_node(data: &[u8]) -> Result<(Node<'_>, usize)> {
+    if data.len() < 6 {
+        return Err(WinverError::Malformed {
+            reason: "truncated version info node header",
+        });
+    }
+    let w_length = u16::from_le_bytes([data[0], data[1]]) as usize;
+    let w_value_length = u16::from_le_bytes([data[2], data[3]]) as usize;
+    let w_type = u16::from_le_bytes([data[4], data[5]]);
+    if w_length < 6 || w_length > data.len() {
+        return Err(WinverError::Malformed {
+            reason: "version info node length out of range",
+        });
+    }
+    let node = &data[..w_length];
+
+    // szKey: null-terminated UTF-16 string starting right after the header
+    let mut key_units = Vec::new();
+    let mut offset = 6;
+    loop {
+        if offset + 2 > node.len() {
+            return Err(WinverError::Malformed {
+                reason: "unterminated szKey in version info node",
+            });
+        }
+        let unit = u16::from_le_bytes([node[offset], node[offset + 1]]);
+        offset += 2;
+        if unit == 0 {
+            break;
+        }
+        key_units.push(unit);
+    }
+    let key = String::from_utf16_lossy(&key_units);
+
+    let is_text = w_type == 1;
+    let value_start = align4(offset);
+    let value_len = if is_text { w_value_length * 2 } else { w_value_length };
+    let value_end = value_start
+        .checked_add(value_len)
+        .filter(|&end| end <= node.len())
+        .ok_or(WinverError::Malformed {
+            reason: "version info value extends past node end",
+        })?;
+    let value = &node[value_start..value_end];
+
+    let children_start = align4(value_end).min(node.len());
+    let children = &node[children_start..];
+
+    Ok((Node { key, value, children }, w_length))
+}
+
+/// Parse every sibling node packed into `data`, stopping at the first all-zero padding.
+fn parse_siblings(mut data: &[u8]) -> Result<Vec<Node<'_>>> {
+    let mut nodes = Vec::new();
+    while data.len() >= 6 {
+        let w_length = u16::from_le_bytes([data[0], data[1]]) as usize;
+        if w_length == 0 {
+            break;
+        }
+        let (node, consumed) = parse_node(data)?;
+        nodes.push(node);
+        let consumed = align4(consumed);
+        if consumed == 0 || consumed > data.len() {
+            break;
+        }
+        data = &data[consumed..];
+    }
+    Ok(nodes)
+}
+
+/// Decode a text node's value (a UTF-16 string, with or without a trailing null) into a `String`.
+fn text_value(value: &[u8]) -> String {
+    let units: Vec<u16> = value
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    let end = units.iter().position(|&c| c == 0).unwrap_or(units.len());
+    String::from_utf16_lossy(&units[..end])
+}
+
+/// Signature every `VS_FIXEDFILEINFO` block starts with.
+const VS_FFI_SIGNATURE: u32 = 0xfeef04bd;
+
+/// Number of DWORDs in a `VS_FIXEDFILEINFO` block.
+const VS_FFI_DWORDS: usize = 13;
+
+/// Parse a raw `VS_FIXEDFILEINFO` block (the value of the root `VS_VERSION_INFO` node) into a
+/// [FixedFileInfo]. Used both by [parse_version_info] and, on Windows, as the parser behind
+/// `get_file_fixed_info`.
+pub(crate) fn parse_fixed_file_info(bytes: &[u8]) -> Result<FixedFileInfo> {
+    let expected = VS_FFI_DWORDS * 4;
+    if bytes.len() < expected {
+        return Err(WinverError::TruncatedFixedInfo {
+            expected,
+            got: bytes.len(),
+        });
+    }
+
+    let dword = |i: usize| -> u32 {
+        let o = i * 4;
+        u32::from_le_bytes([bytes[o], bytes[o + 1], bytes[o + 2], bytes[o + 3]])
+    };
+
+    let signature = dword(0);
+    if signature != VS_FFI_SIGNATURE {
+        return Err(WinverError::BadSignature { found: signature });
+    }
+
+    #[inline]
+    fn combine_dwords(high: u32, low: u32) -> u64 {
+        ((high as u64) << 32) | (low as u64)
+    }
+
+    Ok(FixedFileInfo {
+        struc_version: dword(1),
+        file_version: combine_dwords(dword(2), dword(3)),
+        product_version: combine_dwords(dword(4), dword(5)),
+        file_flags_mask: dword(6),
+        file_flags: dword(7),
+        file_os: dword(8),
+        file_type: dword(9),
+        file_subtype: dword(10),
+        file_date: combine_dwords(dword(11), dword(12)),
+    })
+}
+
+/// A fully-parsed `VS_VERSIONINFO` resource tree.
+#[derive(Debug, Clone, Default)]
+pub struct VersionInfo {
+    /// The root `VS_FIXEDFILEINFO` block.
+    pub fixed_info: FixedFileInfo,
+    /// Every `StringTable`, keyed by its `<lang><codepage>` block name, in turn mapping each
+    /// `String` entry name (e.g. `"ProductName"`) to its value.
+    pub string_tables: HashMap<String, HashMap<String, String>>,
+    /// The `(language, codepage)` pairs listed in `\VarFileInfo\Translation`.
+    pub translations: Vec<(u16, u16)>,
+}
+
+/// Parse a complete `VS_VERSIONINFO` resource tree in pure Rust, with no Win32 dependency.
+///
+/// `data` is the raw resource data: the same bytes `GetFileVersionInfoW` returns on Windows, or
+/// bytes extracted from a PE file's `RT_VERSION` resource by other means.
+pub fn parse_version_info(data: &[u8]) -> Result<VersionInfo> {
+    let (root, _) = parse_node(data)?;
+    if root.key != "VS_VERSION_INFO" {
+        return Err(WinverError::Malformed {
+            reason: "expected root key \"VS_VERSION_INFO\"",
+        });
+    }
+
+    let fixed_info = parse_fixed_file_info(root.value)?;
+    let mut string_tables = HashMap::new();
+    let mut translations = Vec::new();
+
+    for child in parse_siblings(root.children)? {
+        match child.key.as_str() {
+            "StringFileInfo" => {
+                for table in parse_siblings(child.children)? {
+                    let mut strings = HashMap::new();
+                    for string_node in parse_siblings(table.children)? {
+                        strings.insert(string_node.key.clone(), text_value(string_node.value));
+                    }
+                    string_tables.insert(table.key, strings);
+                }
+            }
+            "VarFileInfo" => {
+                for var in parse_siblings(child.children)? {
+                    if var.key == "Translation" {
+                        translations = var
+                            .value
+                            .chunks_exact(4)
+                            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                            .map(|dw| ((dw & 0xffff) as u16, (dw >> 16) as u16))
+                            .collect();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(VersionInfo {
+        fixed_info,
+        string_tables,
+        translations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// UTF-16LE encoding of `s` with a trailing null code unit, as `szKey`/text values use.
+    fn utf16_nul(s: &str) -> Vec<u8> {
+        let mut buf: Vec<u8> = s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        buf.extend_from_slice(&0u16.to_le_bytes());
+        buf
+    }
+
+    /// Pad `buf` with zero bytes up to the next 4-byte boundary.
+    fn pad4(buf: &mut Vec<u8>) {
+        let pad = (4 - buf.len() % 4) % 4;
+        buf.resize(buf.len() + pad, 0);
+    }
+
+    /// Build one VS_VERSIONINFO-style node: header, null-terminated UTF-16 key, padding, value,
+    /// padding, then the already-built bytes of any children.
+    fn build_node(key: &str, w_type: u16, value: &[u8], children: &[u8]) -> Vec<u8> {
+        let mut buf = vec![0u8; 6];
+        buf.extend_from_slice(&utf16_nul(key));
+        pad4(&mut buf);
+        let w_value_length = if w_type == 1 { value.len() / 2 } else { value.len() };
+        buf.extend_from_slice(value);
+        pad4(&mut buf);
+        buf.extend_from_slice(children);
+
+        let w_length = buf.len() as u16;
+        buf[0..2].copy_from_slice(&w_length.to_le_bytes());
+        buf[2..4].copy_from_slice(&(w_value_length as u16).to_le_bytes());
+        buf[4..6].copy_from_slice(&w_type.to_le_bytes());
+        buf
+    }
+
+    /// Concatenate sibling nodes, padding each to a 4-byte boundary first, as `parse_siblings`
+    /// expects between consecutive nodes.
+    fn concat_siblings(nodes: &[Vec<u8>]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for node in nodes {
+            buf.extend_from_slice(node);
+            pad4(&mut buf);
+        }
+        buf
+    }
+
+    fn fixed_file_info_bytes() -> Vec<u8> {
+        let dwords: [u32; VS_FFI_DWORDS] = [
+            VS_FFI_SIGNATURE,
+            0x0001_0000, // struc_version
+            1,           // file_version MS
+            2,           // file_version LS
+            0,           // product_version MS
+            0,           // product_version LS
+            0,           // file_flags_mask
+            0,           // file_flags
+            0x0004_0004, // file_os: VOS_NT_WINDOWS32
+            0x0000_0001, // file_type: VFT_APP
+            0,           // file_subtype
+            0,           // file_date MS
+            0,           // file_date LS
+        ];
+        dwords.iter().flat_map(|d| d.to_le_bytes()).collect()
+    }
+
+    fn sample_version_info_bytes() -> Vec<u8> {
+        let string_node = build_node("ProductName", 1, &utf16_nul("Test Product"), &[]);
+        let table_node = build_node("040904b0", 0, &[], &string_node);
+        let string_file_info = build_node("StringFileInfo", 0, &[], &table_node);
+
+        let translation: u32 = (0x04b0 << 16) | 0x0409;
+        let var_node = build_node("Translation", 0, &translation.to_le_bytes(), &[]);
+        let var_file_info = build_node("VarFileInfo", 0, &[], &var_node);
+
+        let children = concat_siblings(&[string_file_info, var_file_info]);
+        build_node("VS_VERSION_INFO", 0, &fixed_file_info_bytes(), &children)
+    }
+
+    #[test]
+    fn round_trip() {
+        let data = sample_version_info_bytes();
+        let info = parse_version_info(&data).unwrap();
+
+        assert_eq!(info.fixed_info.file_version, combine(1, 2));
+        assert_eq!(info.translations, vec![(0x0409, 0x04b0)]);
+
+        let table = info.string_tables.get("040904b0").expect("missing string table");
+        assert_eq!(table.get("ProductName").map(String::as_str), Some("Test Product"));
+    }
+
+    fn combine(high: u32, low: u32) -> u64 {
+        ((high as u64) << 32) | (low as u64)
+    }
+
+    #[test]
+    fn truncated_header_is_malformed() {
+        let err = parse_node(&[1, 2, 3]).unwrap_err();
+        assert!(matches!(err, WinverError::Malformed { reason } if reason.contains("truncated")));
+    }
+
+    #[test]
+    fn out_of_range_length_is_malformed() {
+        // wLength claims 100 bytes but the buffer only has 8.
+        let data = [100u8, 0, 0, 0, 1, 0, 0, 0];
+        let err = parse_node(&data).unwrap_err();
+        assert!(matches!(err, WinverError::Malformed { reason } if reason.contains("length out of range")));
+    }
+}